@@ -0,0 +1,659 @@
+//! Full-stack `call/cc` for Rust, built on `setjmp`/`longjmp` and a Boehm GC
+//! managed copy of the native stack.
+//!
+//! The core entry point is [`call_cc`], which reifies the current continuation as
+//! a cloneable [`Continuation`] handle. Continuations are **multi-shot**: the same
+//! handle may be [`resume`](Continuation::resume)d any number of times, each time
+//! re-instating the stack as it was at capture. On top of `call/cc` the crate also
+//! provides delimited continuations ([`reset`]/[`shift`]) and [`dynamic_wind`].
+
+use std::{mem::size_of, cell::{Cell, RefCell}, any::Any, marker::PhantomData, ptr::null_mut, rc::Rc, sync::{Once, atomic::{AtomicUsize, Ordering}}};
+
+/// A wind thunk: a cloneable, re-runnable handle to a `before`/`after` closure.
+type WindThunk = Rc<dyn Fn()>;
+
+use sjlj::{ JumpBuf, setjmp, longjmp };
+use stack::approximate_stack_pointer;
+
+mod stack;
+
+#[allow(dead_code)]
+#[link(name = "gc", kind = "dylib")]
+extern "C" {
+    fn GC_malloc(size: usize) -> *mut u8;
+    fn GC_free(ptr: *mut u8);
+    fn GC_init();
+}
+
+/// Ensures the Boehm GC is initialized exactly once before the first capture.
+static GC_ONCE: Once = Once::new();
+
+/// Lazily initializes the Boehm GC. Called at the top of [`call_cc`] so the crate
+/// works as a library without the embedding binary having to call `GC_init`.
+fn ensure_gc() {
+    GC_ONCE.call_once(|| unsafe { GC_init() });
+}
+
+thread_local! {
+    static STACK_BOUNDS: stack::StackBounds = stack::StackBounds::current_thread_stack_bounds();
+}
+
+/// Returns the size of the native stack
+fn stack_size() -> usize {
+    STACK_BOUNDS.with(|bounds| {
+        (bounds.origin as usize) - (approximate_stack_pointer() as usize)
+    })
+}
+
+/// Returns the start of the native stack
+fn stack_origin() -> usize {
+    STACK_BOUNDS.with(|bounds| {
+        bounds.origin as usize
+    })
+}
+
+
+thread_local! {
+    /// Used to store return value from invocation of continuation
+    static CONT_VAL: Cell<Option<Box<dyn Any>>> = Cell::new(None);
+}
+
+/// Lifecycle of a captured continuation.
+///
+/// Tracking this lets the runtime reject invalid re-entry — most importantly for
+/// escape continuations, which are one-shot and only valid while their defining
+/// frame is still on the stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContState {
+    /// just captured, not yet handed to user code
+    Fresh,
+    /// reified as a [`Continuation`] handle, awaiting invocation
+    Captured,
+    /// re-instated by a resume; stays set after the jump has completed
+    Running,
+    /// a one-shot (escape) continuation that has already fired, or whose
+    /// defining [`call_ec`] frame has returned
+    Invoked,
+}
+
+struct Cont {
+    /// saved continuation state
+    state: JumpBuf,
+    /// size of the captured stack
+    csize: usize,
+    /// start pointer of the native stack
+    cstart: usize,
+    /// end pointer of the native stack
+    cend: usize,
+    /// lifecycle state of the continuation
+    status: ContState,
+    /// whether this is an escape-only continuation (no stack snapshot, one-shot)
+    escape: bool,
+    /// pointer to the captured stack
+    cstack: *mut u8,
+    /// id of the prompt that delimits this continuation, or `0` for an
+    /// undelimited (full-stack) continuation
+    prompt: usize,
+    /// snapshot of the [`dynamic_wind`] stack at capture time, used to replay
+    /// `after`/`before` thunks when the continuation is invoked.
+    ///
+    /// Like the rest of `Cont` this lives in `GC_malloc`'d memory whose `Drop` is
+    /// never run, so this `Vec` (a Rust-allocator allocation, unlike the GC'd
+    /// `cstack`) and its `Rc` handles are intentionally leaked for the lifetime of
+    /// the continuation. Captures are expected to be few and long-lived, so the
+    /// leak is accepted rather than worked around.
+    winds: Vec<(WindThunk, WindThunk)>,
+}
+
+/// Captures current continuation and returns it. 
+/// 
+/// If continuation is invoked, it will return the value passed to continuation.
+/// 
+/// # Safety
+/// 
+/// Read [restore_cont_jump].
+#[inline(never)]
+unsafe fn make_continuation() -> Result<*mut Cont, Box<dyn Any>> {
+    make_continuation_to(stack_origin(), 0)
+}
+
+/// Captures the current continuation but only down to `base` rather than all the
+/// way to [`stack_origin`]. `prompt` records the id of the prompt that delimits
+/// the captured region (`0` meaning the whole stack). This is the shared core of
+/// both the undelimited [`call_cc`] and the delimited [`shift`].
+///
+/// # Safety
+///
+/// Read [`restore_cont_jump`].
+#[inline(never)]
+unsafe fn make_continuation_to(base: usize, prompt: usize) -> Result<*mut Cont, Box<dyn Any>> {
+    // Full-stack and delimited captures both funnel through here, so initialize
+    // the GC here too: the delimited subsystem (`reset`/`shift`) can run without
+    // ever touching `call_cc`, and would otherwise `GC_malloc` against an uninitialized GC.
+    ensure_gc();
+
+    let addr = approximate_stack_pointer() as usize;
+
+    let start_stack = base;
+
+    let csize;
+    let cstart;
+    let cend;
+    // compute the size of the stack and its end
+    if addr < start_stack {
+        csize = start_stack - addr;
+        cstart = addr;
+        cend = start_stack;
+    } else {
+        csize = addr - start_stack;
+        cstart = start_stack;
+        cend = addr;
+    }
+
+    let cont = GC_malloc(size_of::<Cont>()) as *mut Cont;
+    (*cont).csize = csize;
+    (*cont).cstart = cstart;
+    (*cont).cend = cend;
+    (*cont).status = ContState::Fresh;
+    (*cont).escape = false;
+    (*cont).prompt = prompt;
+    // `cont` points at uninitialized GC memory, so the `Vec` must be written in
+    // place rather than assigned over the garbage that is already there.
+    core::ptr::addr_of_mut!((*cont).winds).write(WINDS.with(|w| w.borrow().clone()));
+    (*cont).cstack = GC_malloc(csize);
+    // copy native stack to the continuation
+    libc::memcpy((*cont).cstack as _, cstart as _, csize);
+
+    if setjmp(&mut (*cont).state) == 0 {
+        // continuation is fresh, return it
+        Ok(cont)
+    } else {
+        // continuation was invoked, return the value
+        let val = CONT_VAL.with(|cell| cell.replace(None).unwrap());
+        Err(val)
+    }
+}
+
+/// Restores the continuation and jumps to it.
+///
+/// The live frame must sit below the region the saved stack will be memcpy'd
+/// into, otherwise the copy would clobber the frame doing the copying. Rather
+/// than recurse until the stack happens to be deep enough, compute the shortfall
+/// once and reserve exactly that many bytes in the current frame with a runtime
+/// `alloca`-style reservation.
+///
+/// # Safety
+///
+/// Inheretely unsafe, because it uses `longjmp` to jump to the continuation. All local variables that depend
+/// on destructors will be broken.
+#[inline(never)]
+unsafe fn restore_cont_jump(k: *mut Cont, dest: usize) -> ! {
+    /// head-room kept below the saved region for the copying frame itself
+    const MARGIN: usize = 1024;
+
+    // The copying frame must sit *below* the whole destination region, not just
+    // below `csize` bytes: for a delimited cont `dest` is far under the origin, so
+    // reserving `csize` alone would leave this frame inside `[dest, dest+csize)`
+    // and the memcpy would clobber it. Reserve against the region's bottom.
+    let depth = (stack_origin() as isize - dest as isize).max(0) as usize;
+    let need = (depth + MARGIN).saturating_sub(stack_size());
+    if need > 0 {
+        // Push the live frame down by exactly `need` bytes, then copy and jump.
+        stackalloc::alloca(need, |_buf| do_restore_cont_jump(k, dest))
+    } else {
+        do_restore_cont_jump(k, dest)
+    }
+}
+
+/// Performs the actual stack splice: copy the saved slice back to `dest` (the
+/// bottom of the region it should occupy) and `longjmp` into the saved state.
+/// Only called once enough head-room has been reserved by [`restore_cont_jump`].
+#[inline(never)]
+unsafe fn do_restore_cont_jump(k: *mut Cont, dest: usize) -> ! {
+    (*k).status = ContState::Running;
+    libc::memcpy(dest as _, (*k).cstack as _, (*k).csize);
+    longjmp(&(*k).state, 1);
+}
+
+/// Resolves where a continuation's saved slice must be spliced back in.
+///
+/// A full-stack continuation (`prompt == 0`) restores to its original absolute
+/// address. A delimited continuation records the prompt that delimits it, and may
+/// only be reinstated while that prompt still sits at its capture-time stack
+/// pointer: the `longjmp` that follows restores the absolute SP/frame-pointer
+/// image saved at capture, so the slice has to land exactly where it was taken
+/// from — copying it to a shifted address would leave execution running on stack
+/// the register image does not match. We therefore consult `prompt` to validate
+/// that its frame is still installed at the same SP and error otherwise, rather
+/// than silently corrupting the stack.
+///
+/// (Genuinely relocating a delimited continuation under a different dynamic extent
+/// would require rewriting the saved SP/FP in the `JumpBuf`, which this
+/// copy-and-`longjmp` scheme cannot do portably.)
+unsafe fn restore_dest(k: *mut Cont) -> usize {
+    if (*k).prompt == 0 {
+        return (*k).cstart;
+    }
+    let current_sp = PROMPTS.with(|p| {
+        p.borrow()
+            .iter()
+            .rev()
+            .find(|frame| frame.id == (*k).prompt)
+            .map(|frame| frame.sp)
+    });
+    match current_sp {
+        // `cend` is the prompt's SP at capture time; only an exact match is safe.
+        Some(sp) if sp == (*k).cend => (*k).cstart,
+        Some(_) => panic!(
+            "delimited continuation reinstated with its prompt (id {}) at a different \
+             stack pointer than at capture",
+            (*k).prompt
+        ),
+        None => panic!(
+            "delimited continuation reinstated without its delimiting prompt (id {}) installed",
+            (*k).prompt
+        ),
+    }
+}
+
+/// Restores the continuation and jumps to it.
+/// 
+/// # Safety
+/// 
+/// Read [`restore_cont_jump`].
+unsafe fn restore_continuation<T: Any>(k: *mut Cont, value: T) -> ! {
+    if (*k).escape {
+        // Escape continuations are one-shot and valid only while their defining
+        // frame is still live; refuse to jump into a frame that has returned.
+        match (*k).status {
+            ContState::Fresh | ContState::Captured => {}
+            other => panic!(
+                "escape continuation invoked after its frame returned (state: {:?})",
+                other
+            ),
+        }
+        (*k).status = ContState::Invoked;
+        CONT_VAL.with(|cell| cell.set(Some(Box::new(value))));
+        rewind_to(&(*k).winds);
+        // No snapshot to splice back: just jump upward to the capture point.
+        longjmp(&(*k).state, 1);
+    }
+
+    CONT_VAL.with(|cell| cell.set(Some(Box::new(value))));
+    // replay the wind stack so `after`/`before` thunks straddle the jump.
+    rewind_to(&(*k).winds);
+    let dest = restore_dest(k);
+    restore_cont_jump(k, dest);
+}
+
+/// Captures an escape-only continuation: records the capture frame's stack
+/// pointer but skips snapshotting the stack entirely, so it is cheap. It still
+/// allocates the `Cont` record (via `GC_malloc`) and clones the wind stack into
+/// it. It can only be used to jump *upward* while the [`call_ec`] frame that
+/// re-arms its jump buffer lives.
+///
+/// # Safety
+///
+/// Read [`restore_cont_jump`].
+#[inline(never)]
+unsafe fn make_escape_continuation() -> Result<*mut Cont, Box<dyn Any>> {
+    let addr = approximate_stack_pointer() as usize;
+
+    let cont = GC_malloc(size_of::<Cont>()) as *mut Cont;
+    (*cont).csize = 0;
+    (*cont).cstart = addr;
+    (*cont).cend = addr;
+    (*cont).status = ContState::Fresh;
+    (*cont).escape = true;
+    (*cont).prompt = 0;
+    core::ptr::addr_of_mut!((*cont).winds).write(WINDS.with(|w| w.borrow().clone()));
+    (*cont).cstack = null_mut();
+
+    if setjmp(&mut (*cont).state) == 0 {
+        Ok(cont)
+    } else {
+        let val = CONT_VAL.with(|cell| cell.replace(None).unwrap());
+        Err(val)
+    }
+}
+
+/// A cloneable, opaque handle to a captured continuation.
+///
+/// The type parameter `T` is the value type threaded through the capturing
+/// [`call_cc`]: [`resume`](Continuation::resume) re-instates the continuation and
+/// makes its argument the result of that `call_cc`. Handles are multi-shot — clone
+/// one before resuming if you want to resume it again later.
+pub struct Continuation<T> {
+    cont: *mut Cont,
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for Continuation<T> {
+    fn clone(&self) -> Self {
+        Continuation { cont: self.cont, _marker: PhantomData }
+    }
+}
+
+impl<T> Continuation<T> {
+    /// Returns the current lifecycle [`ContState`] of this continuation.
+    pub fn state(&self) -> ContState {
+        unsafe { (*self.cont).status }
+    }
+
+    /// Whether this is an escape-only (one-shot) continuation, as produced by
+    /// [`call_ec`].
+    pub fn is_escape(&self) -> bool {
+        unsafe { (*self.cont).escape }
+    }
+}
+
+impl<T: Any> Continuation<T> {
+    /// Re-instates the continuation, making `value` the result of the [`call_cc`]
+    /// that captured it. Never returns to the caller.
+    ///
+    /// # Safety
+    ///
+    /// Read [`restore_cont_jump`]: the jump skips every Rust destructor in the
+    /// unwound frames, and a multi-shot resume re-enters a frame whose owned data
+    /// may already have been moved or dropped. It is the caller's responsibility
+    /// to ensure no live frame between here and the capture point owns data that
+    /// must be dropped or must not be observed twice.
+    pub unsafe fn resume(self, value: T) -> ! {
+        restore_continuation(self.cont, value)
+    }
+}
+
+
+thread_local! {
+    /// LIFO stack of `(before, after)` thunk pairs installed by [`dynamic_wind`].
+    static WINDS: RefCell<Vec<(WindThunk, WindThunk)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `body` between `before` and `after` thunks, guaranteeing — as in
+/// SCM/Guile — that `after` runs whenever control leaves `body` and `before`
+/// runs whenever control re-enters it, even across continuation jumps that
+/// otherwise bypass Rust destructors (see [`restore_cont_jump`]).
+///
+/// The `(before, after)` closures are wrapped in `Rc` handles and pushed onto a
+/// thread-local wind stack for the duration of `body`; continuations snapshot
+/// that stack (cloning the handles) at capture time and diff it against the live
+/// stack on invocation. Because they are closures, a `before`/`after` pair can
+/// capture and clean up a specific resource.
+///
+/// # Safety
+///
+/// Read [`restore_cont_jump`]: `body` may capture or invoke continuations whose
+/// jumps bypass Rust destructors, which is what makes the `before`/`after`
+/// replay necessary in the first place.
+#[inline(never)]
+pub unsafe fn dynamic_wind<T>(
+    before: impl Fn() + 'static,
+    body: impl FnOnce() -> T,
+    after: impl Fn() + 'static,
+) -> T {
+    let before: WindThunk = Rc::new(before);
+    let after: WindThunk = Rc::new(after);
+    before();
+    WINDS.with(|w| w.borrow_mut().push((before.clone(), after.clone())));
+    let result = body();
+    WINDS.with(|w| {
+        w.borrow_mut().pop().expect("wind stack underflow");
+    });
+    after();
+    result
+}
+
+/// Reconciles the live wind stack with `target` (the stack captured in a `Cont`):
+/// runs the `after` thunk of every frame being exited, innermost first, then the
+/// `before` thunk of every frame being entered, outermost first. Called just
+/// before a continuation splices its stack back in.
+unsafe fn rewind_to(target: &[(WindThunk, WindThunk)]) {
+    let current = WINDS.with(|w| w.borrow().clone());
+    let common = current
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| Rc::ptr_eq(&a.0, &b.0) && Rc::ptr_eq(&a.1, &b.1))
+        .count();
+
+    // leave the frames we are exiting, innermost first.
+    for (_, after) in current[common..].iter().rev() {
+        after();
+    }
+    // enter the frames we are entering, outermost first.
+    for (before, _) in target[common..].iter() {
+        before();
+    }
+
+    WINDS.with(|w| *w.borrow_mut() = target.to_vec());
+}
+
+thread_local! {
+    /// LIFO stack of currently installed prompt frames (innermost last).
+    static PROMPTS: RefCell<Vec<PromptFrame>> = const { RefCell::new(Vec::new()) };
+    /// Used to carry the result of a shift body back to the aborted prompt.
+    static PROMPT_VAL: Cell<Option<Box<dyn Any>>> = const { Cell::new(None) };
+}
+
+/// Hands out ids for prompt frames so that a captured delimited continuation can
+/// remember which prompt it was delimited by. `0` is reserved for "no prompt".
+static PROMPT_COUNTER: AtomicUsize = AtomicUsize::new(1);
+
+/// A marked point on the native stack installed by [`reset`].
+struct PromptFrame {
+    /// approximate stack pointer recorded when the prompt was installed
+    sp: usize,
+    /// jump buffer used to abort back to the prompt
+    buf: JumpBuf,
+    /// unique id of the prompt
+    id: usize,
+}
+
+/// Installs a prompt (a.k.a. `reset`) and runs `f` underneath it.
+///
+/// The prompt marks a point on the stack that a subsequent [`shift`] can capture
+/// up to and abort to. On normal return the prompt frame is popped in LIFO order;
+/// if a `shift` aborts to it, the value produced by the shift body is returned
+/// instead.
+///
+/// # Safety
+///
+/// Read [`restore_cont_jump`].
+#[inline(never)]
+pub unsafe fn reset<T: Any>(f: impl FnOnce() -> T) -> T {
+    let id = PROMPT_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut frame = PromptFrame {
+        sp: approximate_stack_pointer() as usize,
+        buf: core::mem::zeroed(),
+        id,
+    };
+
+    if setjmp(&mut frame.buf) == 0 {
+        // Moving the `JumpBuf` bytes into the vec is fine: `longjmp` only reads the
+        // saved register image, not the buffer's own address.
+        PROMPTS.with(|p| p.borrow_mut().push(frame));
+        let result = f();
+        // normal return: our frame must be the innermost one.
+        PROMPTS.with(|p| {
+            let popped = p.borrow_mut().pop().expect("prompt stack underflow");
+            debug_assert_eq!(popped.id, id, "prompt frames popped out of LIFO order");
+        });
+        result
+    } else {
+        // a shift aborted to us, carrying the shift body's result.
+        let val = PROMPT_VAL.with(|cell| cell.replace(None)).unwrap();
+        *val.downcast().unwrap()
+    }
+}
+
+/// A reified delimited continuation produced by [`shift`].
+///
+/// Applying it memcpys the captured stack slice back to its original address and
+/// `longjmp`s into the saved state, reinstating the delimited region.
+#[derive(Clone, Copy)]
+pub struct DelimCont {
+    cont: *mut Cont,
+}
+
+impl DelimCont {
+    /// Reinstates the delimited continuation, making `value` the result of the
+    /// `shift` expression that captured it.
+    ///
+    /// # Safety
+    ///
+    /// Read [`restore_cont_jump`].
+    pub unsafe fn reinstate<V: Any>(self, value: V) -> ! {
+        restore_continuation(self.cont, value)
+    }
+}
+
+/// Aborts to the prompt with id `id`, discarding every frame installed on top of
+/// it, and carries `value` back as the result of the enclosing [`reset`].
+///
+/// # Safety
+///
+/// Read [`restore_cont_jump`].
+unsafe fn abort_to_prompt<T: Any>(id: usize, value: T) -> ! {
+    PROMPT_VAL.with(|cell| cell.set(Some(Box::new(value))));
+    let buf = PROMPTS.with(|p| {
+        let mut prompts = p.borrow_mut();
+        let pos = prompts
+            .iter()
+            .rposition(|frame| frame.id == id)
+            .expect("abort to a prompt that is no longer installed");
+        // read the jump buffer out bitwise so we don't depend on `JumpBuf: Copy`.
+        let buf = core::ptr::read(&prompts[pos].buf);
+        prompts.truncate(pos);
+        buf
+    });
+    longjmp(&buf, 1);
+}
+
+/// Captures the continuation between the current point and the nearest enclosing
+/// prompt, reifies it, and passes it to `f`. The result of `f` becomes the result
+/// of the enclosing [`reset`].
+///
+/// Only the stack slice delimited by the prompt is copied. Because the saved
+/// register image restores the absolute SP/FP, the captured continuation may only
+/// be reinstated while its delimiting prompt still sits at its capture-time stack
+/// pointer (see [`restore_dest`]); reinstating it once the enclosing [`reset`] has
+/// returned panics rather than corrupting the stack.
+///
+/// # Safety
+///
+/// Read [`restore_cont_jump`].
+#[inline(never)]
+pub unsafe fn shift<T: Any>(f: impl FnOnce(DelimCont) -> T) -> T {
+    let (base, id) = PROMPTS.with(|p| {
+        let prompts = p.borrow();
+        let top = prompts.last().expect("shift without an enclosing reset");
+        (top.sp, top.id)
+    });
+
+    match make_continuation_to(base, id) {
+        Ok(cont) => {
+            // fresh capture: run the shift body and abort its result to the prompt.
+            let result = f(DelimCont { cont });
+            abort_to_prompt(id, result)
+        }
+        // the continuation was reinstated; yield the value passed to `reinstate`.
+        Err(val) => *val.downcast().unwrap(),
+    }
+}
+
+/// Calls `f` with the current continuation reified as a [`Continuation`] handle.
+///
+/// If `f` returns normally, its value is returned. If the continuation is
+/// [`resume`](Continuation::resume)d — whether from inside `f` or from anywhere
+/// the handle has escaped to — control re-enters this `call_cc`, which returns the
+/// value passed to `resume`. Because continuations are multi-shot, this can happen
+/// any number of times.
+///
+/// # Safety
+///
+/// Read [`restore_cont_jump`]. Capturing is harmless, but the reified
+/// [`Continuation`] can be [`resume`](Continuation::resume)d — possibly more than
+/// once — and that jump skips Rust destructors and re-enters frames whose owned
+/// data may already be gone. `call_cc` is therefore `unsafe`: the caller owns the
+/// obligation to use the handle soundly.
+#[inline(never)]
+pub unsafe fn call_cc<F, T>(f: F) -> T
+where
+    F: FnOnce(Continuation<T>) -> T,
+    T: Any,
+{
+    ensure_gc();
+    {
+        match make_continuation() {
+            Ok(k) => {
+                if (*k).status == ContState::Fresh {
+                    (*k).status = ContState::Captured;
+                    f(Continuation { cont: k, _marker: PhantomData })
+                } else {
+                    restore_cont_jump(k, restore_dest(k))
+                }
+            }
+            // continuation was resumed
+            Err(val) => *val.downcast().unwrap(),
+        }
+    }
+}
+
+/// Drop guard that invalidates an escape continuation when its defining
+/// [`call_ec`] frame returns normally, so the handle can no longer be used to
+/// `longjmp` into the now-dead frame.
+struct EscapeFrame(*mut Cont);
+
+impl Drop for EscapeFrame {
+    fn drop(&mut self) {
+        unsafe { (*self.0).status = ContState::Invoked };
+    }
+}
+
+/// Calls `f` with an escape-only continuation — a cheap, non-reentrant
+/// control-flow path, as in Ruby's escape continuations. No stack is copied; the
+/// capture still allocates a small continuation record and a copy of the
+/// [`dynamic_wind`] stack.
+///
+/// The handle may only be [`resume`](Continuation::resume)d once, and only while
+/// this `call_ec` frame is still on the stack (a downward/escape jump). Invoking
+/// it after the frame has returned, or a second time, panics rather than
+/// corrupting memory. For full, multi-shot continuations use [`call_cc`].
+///
+/// # Safety
+///
+/// Read [`restore_cont_jump`]. Resuming the handle `longjmp`s and so skips Rust
+/// destructors on the escaped-over frames; as with [`call_cc`] the caller owns
+/// the obligation to use the handle soundly.
+#[inline(never)]
+pub unsafe fn call_ec<F, T>(f: F) -> T
+where
+    F: FnOnce(Continuation<T>) -> T,
+    T: Any,
+{
+    ensure_gc();
+    // `f` is consumed on the capture path; the resume path must not drop it again.
+    let f = core::mem::ManuallyDrop::new(f);
+    {
+        match make_escape_continuation() {
+            Ok(k) => {
+                // `make_escape_continuation` has returned, so the jump buffer it
+                // filled points into a dead frame. Re-arm it in this frame, which
+                // stays live for as long as the handle may be resumed.
+                if setjmp(&mut (*k).state) != 0 {
+                    let val = CONT_VAL.with(|cell| cell.replace(None).unwrap());
+                    return *val.downcast().unwrap();
+                }
+                (*k).status = ContState::Captured;
+                // Mark the cont `Invoked` as soon as this frame unwinds normally,
+                // so a later `resume` through the escaped handle hits the guard in
+                // `restore_continuation` instead of `longjmp`ing into a dead frame.
+                // When the handle is resumed the jump skips this drop, which is
+                // fine: `restore_continuation` has already set the state.
+                let _frame = EscapeFrame(k);
+                core::mem::ManuallyDrop::into_inner(f)(Continuation { cont: k, _marker: PhantomData })
+            }
+            // the escape continuation was invoked
+            Err(val) => *val.downcast().unwrap(),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,57 @@
+//! Native stack introspection: where the current thread's stack starts and
+//! (approximately) where its stack pointer currently is. Both are needed to size
+//! and copy the live stack when capturing a continuation.
+
+use std::ptr::null_mut;
+
+/// The extent of a thread's native stack.
+pub struct StackBounds {
+    /// highest address of the stack — where it begins (it grows downward)
+    pub origin: *mut u8,
+    /// lowest address the stack may reach
+    #[allow(dead_code)]
+    pub bound: *mut u8,
+}
+
+impl StackBounds {
+    /// Queries the bounds of the stack belonging to the calling thread.
+    pub fn current_thread_stack_bounds() -> Self {
+        unsafe { Self::current_thread_stack_bounds_internal() }
+    }
+
+    #[cfg(target_os = "linux")]
+    unsafe fn current_thread_stack_bounds_internal() -> Self {
+        let mut attr: libc::pthread_attr_t = std::mem::zeroed();
+        assert!(
+            libc::pthread_getattr_np(libc::pthread_self(), &mut attr) == 0,
+            "pthread_getattr_np failed to query the current thread's stack"
+        );
+        let mut bound: *mut libc::c_void = null_mut();
+        let mut size: libc::size_t = 0;
+        let rc = libc::pthread_attr_getstack(&attr, &mut bound, &mut size);
+        libc::pthread_attr_destroy(&mut attr);
+        // Returning bogus bounds here would make `stack_size` underflow and the
+        // capture `memcpy` run off into unmapped memory, so fail loudly instead.
+        assert!(rc == 0 && !bound.is_null(), "pthread_attr_getstack returned no stack");
+        let bound = bound as *mut u8;
+        StackBounds { origin: bound.add(size), bound }
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe fn current_thread_stack_bounds_internal() -> Self {
+        let thread = libc::pthread_self();
+        let origin = libc::pthread_get_stackaddr_np(thread) as *mut u8;
+        let size = libc::pthread_get_stacksize_np(thread);
+        StackBounds { origin, bound: origin.sub(size) }
+    }
+}
+
+/// Returns a pointer close to the current top of the native stack.
+///
+/// The address of a fresh stack local is a good enough approximation for sizing
+/// the region to copy; callers only ever use it as an integer.
+#[inline(never)]
+pub fn approximate_stack_pointer() -> *mut u8 {
+    let mut local = std::mem::MaybeUninit::<*mut u8>::uninit();
+    local.as_mut_ptr() as *mut u8
+}
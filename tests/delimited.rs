@@ -0,0 +1,20 @@
+//! Delimited continuations: `shift` captures the continuation up to the nearest
+//! enclosing `reset`. Not invoking it aborts with the shift body's value;
+//! invoking it resumes the delimited region so the surrounding arithmetic runs.
+
+use call_with_current_continuation_rs::{reset, shift};
+
+#[test]
+fn shift_that_abandons_the_continuation_aborts_to_reset() {
+    // `1 +` is the captured continuation; the body ignores it and returns 10,
+    // which becomes the value of the whole `reset`.
+    let result = unsafe { reset::<u32>(|| 1 + shift::<u32>(|_k| 10)) };
+    assert_eq!(result, 10);
+}
+
+#[test]
+fn shift_that_invokes_the_continuation_resumes_the_delimited_region() {
+    // Reinstating `k` with 5 runs `1 + 5`, and that 6 flows out of `reset`.
+    let result = unsafe { reset::<u32>(|| 1 + shift::<u32>(|k| k.reinstate(5u32))) };
+    assert_eq!(result, 6);
+}
@@ -0,0 +1,51 @@
+//! `dynamic_wind` must run `before`/`after` in order on the normal path and, via
+//! the wind-stack diff, run `after` when a continuation jump exits the wound
+//! region even though `longjmp` skips the Rust unwinder.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use call_with_current_continuation_rs::{call_cc, dynamic_wind};
+
+#[test]
+fn before_and_after_run_in_order_on_normal_exit() {
+    let log: Rc<RefCell<Vec<&'static str>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let (b, a, body_log) = (log.clone(), log.clone(), log.clone());
+    let result = unsafe {
+        dynamic_wind(
+            move || b.borrow_mut().push("before"),
+            move || {
+                body_log.borrow_mut().push("body");
+                7u32
+            },
+            move || a.borrow_mut().push("after"),
+        )
+    };
+
+    assert_eq!(result, 7);
+    assert_eq!(*log.borrow(), ["before", "body", "after"]);
+}
+
+#[test]
+fn after_replays_when_a_continuation_jumps_out() {
+    let log: Rc<RefCell<Vec<&'static str>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let (b, a, jump_log) = (log.clone(), log.clone(), log.clone());
+    unsafe {
+        call_cc::<_, ()>(|out| {
+            dynamic_wind(
+                move || b.borrow_mut().push("before"),
+                move || {
+                    jump_log.borrow_mut().push("body");
+                    // jump out of the wound region; the normal `after` below is
+                    // skipped, so the wind-stack diff must replay it.
+                    out.resume(())
+                },
+                move || a.borrow_mut().push("after"),
+            )
+        });
+    }
+
+    assert_eq!(*log.borrow(), ["before", "body", "after"]);
+}
@@ -0,0 +1,57 @@
+//! Escape continuations are one-shot and only valid while their `call_ec` frame
+//! is live: resuming one performs an early escape, but a handle that outlives its
+//! frame must error rather than jump into dead stack.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use call_with_current_continuation_rs::{call_ec, ContState, Continuation};
+
+#[test]
+fn resume_escapes_early_with_its_value() {
+    let result = unsafe {
+        call_ec::<_, u32>(|k| {
+            k.resume(42u32);
+            #[allow(unreachable_code)]
+            0
+        })
+    };
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn escape_handle_reports_escape() {
+    let mut seen = None;
+    let result = unsafe {
+        call_ec::<_, u32>(|k: Continuation<u32>| {
+            seen = Some((k.is_escape(), k.state()));
+            1
+        })
+    };
+    assert_eq!(result, 1);
+    assert_eq!(seen, Some((true, ContState::Captured)));
+}
+
+#[test]
+fn stale_escape_continuation_errors_instead_of_corrupting() {
+    static mut SAVED: Option<Continuation<u32>> = None;
+
+    let value = unsafe {
+        call_ec::<_, u32>(|k| {
+            #[allow(static_mut_refs)]
+            {
+                SAVED = Some(k.clone());
+            }
+            99
+        })
+    };
+    // `call_ec` returned normally, so its frame is gone.
+    assert_eq!(value, 99);
+
+    // Resuming the now-stale handle must panic, not `longjmp` into dead stack.
+    let outcome = catch_unwind(AssertUnwindSafe(|| unsafe {
+        #[allow(static_mut_refs)]
+        let k = SAVED.clone().unwrap();
+        k.resume(1u32);
+    }));
+    assert!(outcome.is_err(), "stale escape continuation should have errored");
+}
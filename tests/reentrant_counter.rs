@@ -0,0 +1,37 @@
+//! The re-entrant counter that used to live in `main`: a continuation captured
+//! once is resumed repeatedly from outside its original `call_cc`, each time
+//! re-entering the same frame with a fresh value. This exercises the multi-shot
+//! semantics of [`Continuation`].
+
+use call_with_current_continuation_rs::{call_cc, Continuation};
+
+// The handle outlives the `call_cc` that produced it, so it is stashed in a
+// global just like the original demo's `RET`.
+static mut RET: Option<Continuation<u32>> = None;
+
+#[test]
+fn reentrant_counter() {
+    // Kept outside the captured stack; a stack local would be reset to its
+    // capture-time value every time the continuation is resumed.
+    static mut COUNT: u32 = 0;
+
+    unsafe {
+        let value = 100 + call_cc::<_, u32>(|k| {
+            RET = Some(k.clone());
+            k.resume(100u32)
+        });
+        // On the first pass the continuation yields 100, so `value` is 200; every
+        // later resume passes the current count, so `value` is 100 + COUNT.
+        if COUNT == 0 {
+            assert_eq!(value, 200);
+        } else {
+            assert_eq!(value, 100 + COUNT);
+        }
+
+        if COUNT < 3 {
+            COUNT += 1;
+            #[allow(static_mut_refs)]
+            RET.clone().unwrap().resume(COUNT);
+        }
+    }
+}